@@ -16,7 +16,6 @@ use std::env;
 use std::ffi::{CString, OsString};
 use std::io::prelude::*;
 use std::io;
-use std::process;
 use std::str::FromStr;
 
 use nix::fcntl::*;
@@ -57,7 +56,7 @@ enum Command {
 /// Ignore SIGTSTP, SIGTTOU, SIGQUIT, SIGTERM.
 fn set_signal_handlers() -> Result<()> {
     let sigact = SigAction::new(SigHandler::SigIgn, SaFlags::empty(), SigSet::empty());
-    for sig in &[SIGTSTP, SIGTTOU, SIGQUIT, SIGTERM] {
+    for sig in &[Signal::SIGTSTP, Signal::SIGTTOU, Signal::SIGQUIT, Signal::SIGTERM] {
         // This safe because we are not setting a handler function. See
         //   https://github.com/nix-rust/nix/issues/90
         //   http://users.rust-lang.org/t/unix-signals-in-rust/733/3
@@ -179,26 +178,16 @@ fn handle_cd(dest: Option<OsString>) -> Result<()> {
 }
 
 fn handle_exec(prog: &CString, args: &[CString]) -> Result<()> {
-    match fork().chain_err(|| "could not fork")? {
-        ForkResult::Parent { child } => {
-            waitpid(child, None)
-                .chain_err(|| "failed to wait on child")?;
-        }
-        ForkResult::Child => {
-            // TODO: new process group.
-            let exe = lookup_exe(prog)?;
-            match execve(exe, &args, &[]) {
-                // execve does not return successfully!
-                Ok(_) => unreachable!(),
-                Err(e) => {
-                    // Ignore error writing error message.
-                    let _ = writeln!(io::stderr(), "execve: {}", e);
-                    // Child must exit instead of continuing back to prompt.
-                    process::exit(1);
-                }
-            }
-        }
-    }
+    let exe = lookup_exe(prog)?;
+    // Put the child in its own process group and hand it a clean slate of
+    // signal dispositions, rather than leaking the ones we ignore above.
+    let child = spawn(exe, args, &[])
+        .pgid(0)
+        .reset_signals(true)
+        .spawn()
+        .chain_err(|| "could not spawn child")?;
+    waitpid(child, None)
+        .chain_err(|| "failed to wait on child")?;
     Ok(())
 }
 
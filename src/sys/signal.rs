@@ -3,10 +3,64 @@
 
 use libc;
 use {Errno, Result};
+use std::fmt;
 use std::mem;
 use std::ptr;
+use std::str::FromStr;
+
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+use sys::time::TimeSpec;
+
+// A first-class enum rather than re-exported raw libc constants, so that only
+// valid signals can be passed to the functions below. The discriminants
+// are the raw signal numbers so `Signal as libc::c_int` round-trips back to
+// the value the kernel expects.
+#[repr(i32)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Signal {
+    SIGHUP = libc::SIGHUP,
+    SIGINT = libc::SIGINT,
+    SIGQUIT = libc::SIGQUIT,
+    SIGILL = libc::SIGILL,
+    SIGTRAP = libc::SIGTRAP,
+    SIGABRT = libc::SIGABRT,
+    SIGBUS = libc::SIGBUS,
+    SIGFPE = libc::SIGFPE,
+    SIGKILL = libc::SIGKILL,
+    SIGUSR1 = libc::SIGUSR1,
+    SIGSEGV = libc::SIGSEGV,
+    SIGUSR2 = libc::SIGUSR2,
+    SIGPIPE = libc::SIGPIPE,
+    SIGALRM = libc::SIGALRM,
+    SIGTERM = libc::SIGTERM,
+    #[cfg(not(target_os = "macos"))]
+    SIGSTKFLT = libc::SIGSTKFLT,
+    SIGCHLD = libc::SIGCHLD,
+    SIGCONT = libc::SIGCONT,
+    SIGSTOP = libc::SIGSTOP,
+    SIGTSTP = libc::SIGTSTP,
+    SIGTTIN = libc::SIGTTIN,
+    SIGTTOU = libc::SIGTTOU,
+    SIGURG = libc::SIGURG,
+    SIGXCPU = libc::SIGXCPU,
+    SIGXFSZ = libc::SIGXFSZ,
+    SIGVTALRM = libc::SIGVTALRM,
+    SIGPROF = libc::SIGPROF,
+    SIGWINCH = libc::SIGWINCH,
+    SIGIO = libc::SIGIO,
+    #[cfg(not(target_os = "macos"))]
+    SIGPWR = libc::SIGPWR,
+    SIGSYS = libc::SIGSYS,
+    #[cfg(target_os = "macos")]
+    SIGEMT = libc::SIGEMT,
+    #[cfg(target_os = "macos")]
+    SIGINFO = libc::SIGINFO,
+}
+
+pub use self::Signal::*;
 
-pub use libc::{
+#[cfg(not(target_os = "macos"))]
+const SIGNALS: [Signal; 31] = [
     SIGHUP,
     SIGINT,
     SIGQUIT,
@@ -22,6 +76,7 @@ pub use libc::{
     SIGPIPE,
     SIGALRM,
     SIGTERM,
+    SIGSTKFLT,
     SIGCHLD,
     SIGCONT,
     SIGSTOP,
@@ -35,23 +90,152 @@ pub use libc::{
     SIGPROF,
     SIGWINCH,
     SIGIO,
-    SIGSYS,
-};
-
+    SIGPWR,
+    SIGSYS];
 #[cfg(target_os = "macos")]
-pub use libc::{
+const SIGNALS: [Signal; 31] = [
+    SIGHUP,
+    SIGINT,
+    SIGQUIT,
+    SIGILL,
+    SIGTRAP,
+    SIGABRT,
+    SIGBUS,
+    SIGFPE,
+    SIGKILL,
+    SIGUSR1,
+    SIGSEGV,
+    SIGUSR2,
+    SIGPIPE,
+    SIGALRM,
+    SIGTERM,
+    SIGCHLD,
+    SIGCONT,
+    SIGSTOP,
+    SIGTSTP,
+    SIGTTIN,
+    SIGTTOU,
+    SIGURG,
+    SIGXCPU,
+    SIGXFSZ,
+    SIGVTALRM,
+    SIGPROF,
+    SIGWINCH,
+    SIGIO,
+    SIGSYS,
     SIGEMT,
-    SIGINFO,
-};
+    SIGINFO];
+
+impl Signal {
+    /// Converts a raw signal number into a `Signal`, returning `EINVAL` when
+    /// the value does not name a signal we know about on this platform.
+    ///
+    /// This is the inverse of `signal as libc::c_int`, and is intended for
+    /// round-tripping values that come back from the kernel (e.g. from
+    /// `SigSet::wait`).
+    pub fn from_c_int(signum: libc::c_int) -> Result<Signal> {
+        match 0 < signum && signum < NSIG {
+            true => Ok(unsafe { mem::transmute(signum) }),
+            false => Err(Errno::EINVAL),
+        }
+    }
 
-#[cfg(not(target_os = "macos"))]
-pub use libc::{
-    SIGPWR,
-    SIGSTKFLT,
-    SIGIOT, // Alias for SIGABRT
-    SIGPOLL, // Alias for SIGIO
-    SIGUNUSED, // Alias for 31
-};
+    /// Iterate through all the signals valid on this platform.
+    pub fn iterator() -> SignalIterator {
+        SignalIterator { next: 0 }
+    }
+
+    /// Returns the canonical `SIG*` name of the signal.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SIGHUP => "SIGHUP",
+            SIGINT => "SIGINT",
+            SIGQUIT => "SIGQUIT",
+            SIGILL => "SIGILL",
+            SIGTRAP => "SIGTRAP",
+            SIGABRT => "SIGABRT",
+            SIGBUS => "SIGBUS",
+            SIGFPE => "SIGFPE",
+            SIGKILL => "SIGKILL",
+            SIGUSR1 => "SIGUSR1",
+            SIGSEGV => "SIGSEGV",
+            SIGUSR2 => "SIGUSR2",
+            SIGPIPE => "SIGPIPE",
+            SIGALRM => "SIGALRM",
+            SIGTERM => "SIGTERM",
+            #[cfg(not(target_os = "macos"))]
+            SIGSTKFLT => "SIGSTKFLT",
+            SIGCHLD => "SIGCHLD",
+            SIGCONT => "SIGCONT",
+            SIGSTOP => "SIGSTOP",
+            SIGTSTP => "SIGTSTP",
+            SIGTTIN => "SIGTTIN",
+            SIGTTOU => "SIGTTOU",
+            SIGURG => "SIGURG",
+            SIGXCPU => "SIGXCPU",
+            SIGXFSZ => "SIGXFSZ",
+            SIGVTALRM => "SIGVTALRM",
+            SIGPROF => "SIGPROF",
+            SIGWINCH => "SIGWINCH",
+            SIGIO => "SIGIO",
+            #[cfg(not(target_os = "macos"))]
+            SIGPWR => "SIGPWR",
+            SIGSYS => "SIGSYS",
+            #[cfg(target_os = "macos")]
+            SIGEMT => "SIGEMT",
+            #[cfg(target_os = "macos")]
+            SIGINFO => "SIGINFO",
+        }
+    }
+}
+
+/// Parses a signal from its canonical `SIG*` name, the short form without the
+/// `SIG` prefix (both case-insensitively), or its raw signal number.
+impl FromStr for Signal {
+    type Err = ();
+
+    fn from_str(s: &str) -> ::std::result::Result<Signal, ()> {
+        // Allow looking up by the raw signal number, e.g. "15" for SIGTERM.
+        if let Ok(num) = s.parse::<libc::c_int>() {
+            return Signal::from_c_int(num).map_err(|_| ());
+        }
+
+        let name = s.to_uppercase();
+        let name = if name.starts_with("SIG") { name } else { format!("SIG{}", name) };
+        for signal in Signal::iterator() {
+            if signal.as_str() == name {
+                return Ok(signal);
+            }
+        }
+        Err(())
+    }
+}
+
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Iterator over all signals valid on the current platform, as returned by
+/// `Signal::iterator`.
+pub struct SignalIterator {
+    next: usize,
+}
+
+impl Iterator for SignalIterator {
+    type Item = Signal;
+
+    fn next(&mut self) -> Option<Signal> {
+        if self.next < SIGNALS.len() {
+            let next_signal = SIGNALS[self.next];
+            self.next += 1;
+            Some(next_signal)
+        } else {
+            None
+        }
+    }
+}
 
 pub const NSIG: libc::c_int = 32;
 
@@ -80,8 +264,6 @@ pub struct SigSet {
     sigset: libc::sigset_t
 }
 
-pub type SigNum = libc::c_int;
-
 impl SigSet {
     pub fn all() -> SigSet {
         let mut sigset: libc::sigset_t = unsafe { mem::uninitialized() };
@@ -97,8 +279,8 @@ impl SigSet {
         SigSet { sigset: sigset }
     }
 
-    pub fn add(&mut self, signum: SigNum) -> Result<()> {
-        let res = unsafe { libc::sigaddset(&mut self.sigset as *mut libc::sigset_t, signum) };
+    pub fn add(&mut self, signal: Signal) -> Result<()> {
+        let res = unsafe { libc::sigaddset(&mut self.sigset as *mut libc::sigset_t, signal as libc::c_int) };
 
         Errno::result(res).map(drop)
     }
@@ -109,22 +291,27 @@ impl SigSet {
         Errno::result(res).map(drop)
     }
 
-    pub fn remove(&mut self, signum: SigNum) -> Result<()> {
-        let res = unsafe { libc::sigdelset(&mut self.sigset as *mut libc::sigset_t, signum) };
+    pub fn remove(&mut self, signal: Signal) -> Result<()> {
+        let res = unsafe { libc::sigdelset(&mut self.sigset as *mut libc::sigset_t, signal as libc::c_int) };
 
         Errno::result(res).map(drop)
     }
 
     pub fn extend(&mut self, other: &SigSet) -> Result<()> {
         for i in 1..NSIG {
-            if try!(other.contains(i)) {
-                try!(self.add(i));
+            if try!(other.contains_raw(i)) {
+                let res = unsafe { libc::sigaddset(&mut self.sigset as *mut libc::sigset_t, i) };
+                try!(Errno::result(res));
             }
         }
         Ok(())
     }
 
-    pub fn contains(&self, signum: SigNum) -> Result<bool> {
+    pub fn contains(&self, signal: Signal) -> Result<bool> {
+        self.contains_raw(signal as libc::c_int)
+    }
+
+    fn contains_raw(&self, signum: libc::c_int) -> Result<bool> {
         let res = unsafe { libc::sigismember(&self.sigset as *const libc::sigset_t, signum) };
 
         match try!(Errno::result(res)) {
@@ -165,11 +352,75 @@ impl SigSet {
 
     /// Suspends execution of the calling thread until one of the signals in the
     /// signal mask becomes pending, and returns the accepted signal.
-    pub fn wait(&self) -> Result<SigNum> {
-        let mut signum: SigNum = unsafe { mem::uninitialized() };
+    pub fn wait(&self) -> Result<Signal> {
+        let mut signum: libc::c_int = unsafe { mem::uninitialized() };
         let res = unsafe { libc::sigwait(&self.sigset as *const libc::sigset_t, &mut signum) };
 
-        Errno::result(res).map(|_| signum)
+        Errno::result(res).and_then(|_| Signal::from_c_int(signum))
+    }
+
+    /// Suspends execution of the calling thread until one of the signals in the
+    /// signal mask becomes pending, and returns its full `siginfo` payload.
+    ///
+    /// This wraps `sigwaitinfo(2)`, which unlike `wait` (backed by `sigwait`)
+    /// surfaces the sender pid, uid and `si_code` of the accepted signal.
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    pub fn wait_info(&self) -> Result<SigInfo> {
+        let mut info: libc::siginfo_t = unsafe { mem::uninitialized() };
+        let res = unsafe { libc::sigwaitinfo(&self.sigset as *const libc::sigset_t, &mut info) };
+
+        Errno::result(res).map(|_| SigInfo { siginfo: info })
+    }
+
+    /// Like `wait_info`, but gives up after `timeout` and returns `Ok(None)`
+    /// when it expires with no pending signal.
+    ///
+    /// Passing `None` waits indefinitely. This wraps `sigtimedwait(2)`; the
+    /// `EAGAIN` it returns on expiry is translated into `Ok(None)` so callers
+    /// can loop on it rather than inspecting the raw errno.
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    pub fn timedwait(&self, timeout: Option<TimeSpec>) -> Result<Option<SigInfo>> {
+        let mut info: libc::siginfo_t = unsafe { mem::uninitialized() };
+        let res = unsafe {
+            libc::sigtimedwait(&self.sigset as *const libc::sigset_t,
+                               &mut info,
+                               timeout.as_ref().map_or(ptr::null(), |ts| ts.as_ref() as *const libc::timespec))
+        };
+
+        match Errno::result(res) {
+            Ok(_) => Ok(Some(SigInfo { siginfo: info })),
+            Err(Errno::EAGAIN) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+/// A safe wrapper around `libc::siginfo_t` as returned by `SigSet::wait_info`
+/// and `SigSet::timedwait`.
+#[derive(Clone, Copy)]
+pub struct SigInfo {
+    siginfo: libc::siginfo_t,
+}
+
+impl SigInfo {
+    /// The signal that was accepted.
+    pub fn signal(&self) -> Result<Signal> {
+        Signal::from_c_int(self.siginfo.si_signo)
+    }
+
+    /// The pid of the sending process, where applicable.
+    pub fn pid(&self) -> libc::pid_t {
+        unsafe { self.siginfo.si_pid() }
+    }
+
+    /// The real uid of the sending process, where applicable.
+    pub fn uid(&self) -> libc::uid_t {
+        unsafe { self.siginfo.si_uid() }
+    }
+
+    /// The `si_code` describing the origin of the signal.
+    pub fn code(&self) -> libc::c_int {
+        self.siginfo.si_code
     }
 }
 
@@ -185,8 +436,8 @@ impl AsRef<libc::sigset_t> for SigSet {
 pub enum SigHandler {
     SigDfl,
     SigIgn,
-    Handler(extern fn(SigNum)),
-    SigAction(extern fn(SigNum, *mut libc::siginfo_t, *mut libc::c_void))
+    Handler(extern fn(libc::c_int)),
+    SigAction(extern fn(libc::c_int, *mut libc::siginfo_t, *mut libc::c_void))
 }
 
 pub struct SigAction {
@@ -201,8 +452,8 @@ impl SigAction {
         s.sa_sigaction = match handler {
             SigHandler::SigDfl => libc::SIG_DFL,
             SigHandler::SigIgn => libc::SIG_IGN ,
-            SigHandler::Handler(f) => f as libc::sighandler_t,
-            SigHandler::SigAction(f) => f as libc::sighandler_t,
+            SigHandler::Handler(f) => f as *const extern fn(libc::c_int) as libc::sighandler_t,
+            SigHandler::SigAction(f) => f as *const extern fn(libc::c_int, *mut libc::siginfo_t, *mut libc::c_void) as libc::sighandler_t,
         };
         s.sa_flags = match handler {
             SigHandler::SigAction(_) => (flags | SA_SIGINFO).bits(),
@@ -214,11 +465,11 @@ impl SigAction {
     }
 }
 
-pub unsafe fn sigaction(signum: SigNum, sigaction: &SigAction) -> Result<SigAction> {
+pub unsafe fn sigaction(signal: Signal, sigaction: &SigAction) -> Result<SigAction> {
     let mut oldact = mem::uninitialized::<libc::sigaction>();
 
     let res =
-        libc::sigaction(signum, &sigaction.sigaction as *const libc::sigaction, &mut oldact as *mut libc::sigaction);
+        libc::sigaction(signal as libc::c_int, &sigaction.sigaction as *const libc::sigaction, &mut oldact as *mut libc::sigaction);
 
     Errno::result(res).map(|_| SigAction { sigaction: oldact })
 }
@@ -257,18 +508,89 @@ pub fn pthread_sigmask(how: SigFlags,
     Errno::result(res).map(drop)
 }
 
-pub fn kill(pid: libc::pid_t, signum: SigNum) -> Result<()> {
-    let res = unsafe { libc::kill(pid, signum) };
+pub fn kill(pid: libc::pid_t, signal: Signal) -> Result<()> {
+    let res = unsafe { libc::kill(pid, signal as libc::c_int) };
 
     Errno::result(res).map(drop)
 }
 
-pub fn raise(signum: SigNum) -> Result<()> {
-    let res = unsafe { libc::raise(signum) };
+pub fn raise(signal: Signal) -> Result<()> {
+    let res = unsafe { libc::raise(signal as libc::c_int) };
 
     Errno::result(res).map(drop)
 }
 
+/// How a `SigEvent` requests to be notified when the associated event (a POSIX
+/// timer expiring, an async-IO request completing, ...) fires.
+///
+/// Mirrors the `sigev_notify` field of `struct sigevent`.
+#[derive(Clone, Copy)]
+pub enum SigevNotify {
+    /// No notification is delivered when the event occurs.
+    SigevNone,
+    /// A signal is sent to the process, carrying `si_value` in its `siginfo`.
+    SigevSignal { signal: Signal, si_value: libc::intptr_t },
+    /// Like `SigevSignal`, but the signal is directed at a specific thread.
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    SigevThreadId { signal: Signal, thread_id: libc::pid_t, si_value: libc::intptr_t },
+}
+
+/// A safe builder around `libc::sigevent`, to be handed to facilities such as
+/// `timer_create` and the `aio_*` family.
+pub struct SigEvent {
+    sigevent: libc::sigevent
+}
+
+impl SigEvent {
+    /// Builds a `sigevent` describing the requested notification.
+    ///
+    /// The whole struct is zero-initialized and only the fields relevant to the
+    /// chosen `SigevNotify` mode are written, since the layout of the trailing
+    /// union varies across platforms.
+    pub fn new(notify: SigevNotify) -> SigEvent {
+        let mut sev: libc::sigevent = unsafe { mem::zeroed() };
+        sev.sigev_notify = match notify {
+            SigevNotify::SigevNone => libc::SIGEV_NONE,
+            SigevNotify::SigevSignal { .. } => libc::SIGEV_SIGNAL,
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            SigevNotify::SigevThreadId { .. } => libc::SIGEV_THREAD_ID,
+        };
+        sev.sigev_signo = match notify {
+            SigevNotify::SigevSignal { signal, .. } => signal as libc::c_int,
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            SigevNotify::SigevThreadId { signal, .. } => signal as libc::c_int,
+            _ => 0,
+        };
+        sev.sigev_value.sival_ptr = match notify {
+            SigevNotify::SigevNone => ptr::null_mut(),
+            SigevNotify::SigevSignal { si_value, .. } => si_value as *mut libc::c_void,
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            SigevNotify::SigevThreadId { si_value, .. } => si_value as *mut libc::c_void,
+        };
+        SigEvent::set_tid(&mut sev, &notify);
+
+        SigEvent { sigevent: sev }
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    fn set_tid(sev: &mut libc::sigevent, notify: &SigevNotify) {
+        sev._sigev_un._tid = match *notify {
+            SigevNotify::SigevThreadId { thread_id, .. } => thread_id,
+            _ => 0,
+        };
+    }
+
+    #[cfg(not(any(target_os = "linux", target_os = "android")))]
+    fn set_tid(_sev: &mut libc::sigevent, _notify: &SigevNotify) {
+    }
+}
+
+impl AsRef<libc::sigevent> for SigEvent {
+    fn as_ref(&self) -> &libc::sigevent {
+        &self.sigevent
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -290,8 +612,8 @@ mod tests {
     fn test_clear() {
         let mut set = SigSet::all();
         set.clear().unwrap();
-        for i in 1..NSIG {
-            assert_eq!(set.contains(i), Ok(false));
+        for signal in SIGNALS.iter() {
+            assert_eq!(set.contains(*signal), Ok(false));
         }
     }
 
@@ -345,4 +667,58 @@ mod tests {
         raise(SIGUSR1).unwrap();
         assert_eq!(mask.wait().unwrap(), SIGUSR1);
     }
+
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    #[test]
+    fn test_timedwait_timeout() {
+        use sys::time::TimeSpec;
+
+        let mut mask = SigSet::empty();
+        mask.add(SIGUSR1).unwrap();
+        mask.thread_block().unwrap();
+
+        // Nothing is pending, so the short timeout should expire.
+        assert!(mask.timedwait(Some(TimeSpec::seconds(0))).unwrap().is_none());
+    }
+
+    #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+    #[test]
+    fn test_wait_info() {
+        let mut mask = SigSet::empty();
+        mask.add(SIGUSR1).unwrap();
+        mask.thread_block().unwrap();
+
+        raise(SIGUSR1).unwrap();
+        let info = mask.wait_info().unwrap();
+        assert_eq!(info.signal().unwrap(), SIGUSR1);
+    }
+
+    #[test]
+    fn test_from_str_round_trips() {
+        for signal in Signal::iterator() {
+            assert_eq!(signal.as_str().parse::<Signal>().unwrap(), signal);
+            assert_eq!((signal as i32).to_string().parse::<Signal>().unwrap(), signal);
+        }
+    }
+
+    #[test]
+    fn test_from_str_invalid_value() {
+        let long_string: String = (0..NSIG).map(|_| 'a').collect();
+        assert!(long_string.parse::<Signal>().is_err());
+        assert!("".parse::<Signal>().is_err());
+        assert!("SIGBOGUS".parse::<Signal>().is_err());
+    }
+
+    #[test]
+    fn test_from_str_short_and_case_insensitive() {
+        assert_eq!("term".parse::<Signal>().unwrap(), SIGTERM);
+        assert_eq!("sigterm".parse::<Signal>().unwrap(), SIGTERM);
+        assert_eq!("TERM".parse::<Signal>().unwrap(), SIGTERM);
+        assert_eq!("15".parse::<Signal>().unwrap(), SIGTERM);
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(SIGTERM.to_string(), "SIGTERM");
+    }
 }
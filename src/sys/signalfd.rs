@@ -0,0 +1,141 @@
+//! Interface for the `signalfd` syscall.
+//!
+//! # Signal handling and threads
+//!
+//! Signal handling in threaded programs can be very tricky. `signalfd` provides
+//! an alternative to the usual `SigAction`-based approach: signals in the given
+//! `SigSet` are delivered synchronously by `read`ing `signalfd_siginfo` structs
+//! from a file descriptor, which composes cleanly with `poll`-based event
+//! loops.
+//!
+//! For this to work the signals in the mask must first be blocked in every
+//! thread with [`SigSet::thread_block`](../signal/struct.SigSet.html#method.thread_block),
+//! otherwise they will be handled according to their default disposition before
+//! `signalfd` ever sees them.
+
+use libc;
+use {Errno, Result};
+use std::mem;
+use std::os::unix::io::{RawFd, AsRawFd};
+
+use sys::signal::SigSet;
+
+pub use libc::signalfd_siginfo as siginfo;
+
+bitflags!{
+    flags SfdFlags: libc::c_int {
+        const SFD_CLOEXEC  = libc::SFD_CLOEXEC,
+        const SFD_NONBLOCK = libc::SFD_NONBLOCK,
+    }
+}
+
+/// Passed to `signalfd` to request a brand new descriptor rather than
+/// re-arming an existing one.
+pub const SIGNALFD_NEW: RawFd = -1;
+const SIGNALFD_SIGINFO_SIZE: usize = 128;
+
+/// Creates a new file descriptor for accepting the signals in `mask`, or
+/// re-arms `fd` if it is not `SIGNALFD_NEW`.
+///
+/// The caller is responsible for blocking the signals in `mask`; see the module
+/// documentation.
+pub fn signalfd(fd: RawFd, mask: &SigSet, flags: SfdFlags) -> Result<RawFd> {
+    unsafe {
+        Errno::result(libc::signalfd(fd as libc::c_int, mask.as_ref(), flags.bits()))
+    }
+}
+
+/// A file descriptor over which the signals in a `SigSet` are delivered.
+///
+/// The signals in the mask must be blocked in the calling thread (see
+/// [`SigSet::thread_block`](../signal/struct.SigSet.html#method.thread_block))
+/// before they can be read here; otherwise they are handled as usual.
+pub struct SignalFd(RawFd);
+
+impl SignalFd {
+    pub fn new(mask: &SigSet) -> Result<SignalFd> {
+        Self::with_flags(mask, SfdFlags::empty())
+    }
+
+    pub fn with_flags(mask: &SigSet, flags: SfdFlags) -> Result<SignalFd> {
+        let fd = try!(signalfd(SIGNALFD_NEW, mask, flags));
+
+        Ok(SignalFd(fd))
+    }
+
+    /// Changes the set of signals accepted by this descriptor by re-invoking
+    /// `signalfd` on the existing fd.
+    pub fn set_mask(&mut self, mask: &SigSet) -> Result<()> {
+        signalfd(self.0, mask, SfdFlags::empty()).map(drop)
+    }
+
+    /// Reads a single pending signal from the descriptor.
+    ///
+    /// Returns `Ok(None)` if the descriptor was opened with `SFD_NONBLOCK` and
+    /// no signal is currently pending.
+    pub fn read_signal(&mut self) -> Result<Option<siginfo>> {
+        let mut buffer = unsafe { mem::uninitialized::<[u8; SIGNALFD_SIGINFO_SIZE]>() };
+
+        let res = Errno::result(unsafe {
+            libc::read(self.0,
+                       buffer.as_mut_ptr() as *mut libc::c_void,
+                       SIGNALFD_SIGINFO_SIZE as libc::size_t)
+        }).map(|r| r as usize);
+
+        match res {
+            Ok(SIGNALFD_SIGINFO_SIZE) => Ok(Some(unsafe { mem::transmute(buffer) })),
+            Ok(_) => unreachable!("partial read on signalfd"),
+            Err(Errno::EAGAIN) => Ok(None),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+impl AsRawFd for SignalFd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0
+    }
+}
+
+impl Drop for SignalFd {
+    fn drop(&mut self) {
+        let _ = unsafe { libc::close(self.0) };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sys::signal::{self, SigSet, SIGUSR1};
+
+    #[test]
+    fn create_signalfd() {
+        let mut mask = SigSet::empty();
+        mask.add(SIGUSR1).unwrap();
+        let fd = SignalFd::new(&mask);
+        assert!(fd.is_ok());
+    }
+
+    #[test]
+    fn read_empty_signalfd() {
+        let mut mask = SigSet::empty();
+        mask.add(SIGUSR1).unwrap();
+        mask.thread_block().unwrap();
+
+        let mut fd = SignalFd::with_flags(&mask, SFD_NONBLOCK).unwrap();
+        assert!(fd.read_signal().unwrap().is_none());
+    }
+
+    #[test]
+    fn read_signal() {
+        let mut mask = SigSet::empty();
+        mask.add(SIGUSR1).unwrap();
+        mask.thread_block().unwrap();
+
+        let mut fd = SignalFd::new(&mask).unwrap();
+
+        signal::raise(SIGUSR1).unwrap();
+        let info = fd.read_signal().unwrap().unwrap();
+        assert_eq!(info.ssi_signo as i32, SIGUSR1 as i32);
+    }
+}
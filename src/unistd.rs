@@ -0,0 +1,101 @@
+//! Safe wrappers around functions found in libc "unistd.h" header
+
+use libc::{self, pid_t};
+use {Errno, Result};
+use std::ffi::CString;
+
+use sys::signal::{self, Signal, SigSet, SigAction, SigHandler, SaFlags, SIG_SETMASK, NSIG};
+
+/// Builder for a safe `fork(2)` + `execve(2)` that fixes up the child's signal
+/// state before handing control to the new program.
+///
+/// Launchers that roll their own `fork`/`exec` routinely leak the parent's
+/// signal handlers and mask into the child (see the `shell` example, which has
+/// to ignore `SIGTSTP`/`SIGTTOU`/`SIGQUIT`/`SIGTERM` in the parent). This
+/// builder performs the async-signal-unsafe-to-get-wrong dance for them: in the
+/// child, between `fork` and `execve`, it can move the child into a process
+/// group, restore every signal disposition to `SIG_DFL`, and install a fresh
+/// signal mask. It mirrors the "set child signal mask / reset handlers at
+/// spawn" capability being added to `std`'s `CommandExt`.
+///
+/// `spawn` returns the child pid to the parent.
+pub struct Spawn<'a> {
+    path: &'a CString,
+    args: &'a [CString],
+    env: &'a [CString],
+    pgid: Option<pid_t>,
+    reset_signals: bool,
+    sigmask: Option<SigSet>,
+}
+
+/// Creates a new `Spawn` builder that will `execve` `path` with `args` and
+/// `env`.
+pub fn spawn<'a>(path: &'a CString, args: &'a [CString], env: &'a [CString]) -> Spawn<'a> {
+    Spawn {
+        path: path,
+        args: args,
+        env: env,
+        pgid: None,
+        reset_signals: false,
+        sigmask: None,
+    }
+}
+
+impl<'a> Spawn<'a> {
+    /// Place the child in the process group `pgid` (via `setpgid`) before
+    /// `execve`. Passing `0` creates a new group led by the child.
+    pub fn pgid(mut self, pgid: pid_t) -> Spawn<'a> {
+        self.pgid = Some(pgid);
+        self
+    }
+
+    /// Restore every signal to its default disposition in the child, undoing
+    /// any handlers inherited from the parent.
+    pub fn reset_signals(mut self, reset: bool) -> Spawn<'a> {
+        self.reset_signals = reset;
+        self
+    }
+
+    /// Install `mask` as the child's signal mask (via `pthread_sigmask` with
+    /// `SIG_SETMASK`) before `execve`.
+    pub fn sigmask(mut self, mask: SigSet) -> Spawn<'a> {
+        self.sigmask = Some(mask);
+        self
+    }
+
+    /// Fork, apply the requested fix-ups in the child, then `execve`. Returns
+    /// the child pid to the parent.
+    pub fn spawn(self) -> Result<pid_t> {
+        match try!(fork()) {
+            ForkResult::Parent { child } => Ok(child),
+            ForkResult::Child => {
+                // Everything below must stay async-signal-safe.
+                if let Some(pgid) = self.pgid {
+                    let _ = setpgid(0, pgid);
+                }
+
+                if self.reset_signals {
+                    let default = SigAction::new(SigHandler::SigDfl,
+                                                 SaFlags::empty(),
+                                                 SigSet::empty());
+                    for signum in 1..NSIG {
+                        if let Ok(signal) = Signal::from_c_int(signum) {
+                            // SIGKILL and SIGSTOP can't be caught or reset; the
+                            // resulting EINVAL is harmless, so ignore errors.
+                            let _ = unsafe { signal::sigaction(signal, &default) };
+                        }
+                    }
+                }
+
+                if let Some(ref mask) = self.sigmask {
+                    let _ = signal::pthread_sigmask(SIG_SETMASK, Some(mask), None);
+                }
+
+                match execve(self.path, self.args, self.env) {
+                    Ok(_) => unreachable!(),
+                    Err(_) => unsafe { libc::_exit(127) },
+                }
+            }
+        }
+    }
+}